@@ -2,13 +2,48 @@ mod models;
 mod routes;
 mod services;
 
+use axum::extract::FromRef;
 use axum::routing::{get, post};
 use axum::Router;
+use models::execution::ExecutionResult;
+use routes::katas::VerifyResult;
+use services::executor::Executor;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tower_http::cors::CorsLayer;
 use tower_http::services::ServeDir;
 
+/// Combined router state. Handlers extract just the piece they need via
+/// `FromRef` below, so most handler signatures are unchanged from when
+/// `Arc<Vec<Kata>>` was the whole state. Playground runs and kata
+/// verification are bounded by separate `Executor`s — their job result
+/// types differ (`ExecutionResult` vs `VerifyResult`) — but each still caps
+/// its own concurrent compiles independently.
+#[derive(Clone)]
+struct AppState {
+    katas: Arc<Vec<models::kata::Kata>>,
+    executor: Arc<Executor<ExecutionResult>>,
+    verify_executor: Arc<Executor<VerifyResult>>,
+}
+
+impl FromRef<AppState> for Arc<Vec<models::kata::Kata>> {
+    fn from_ref(state: &AppState) -> Self {
+        state.katas.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<Executor<ExecutionResult>> {
+    fn from_ref(state: &AppState) -> Self {
+        state.executor.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<Executor<VerifyResult>> {
+    fn from_ref(state: &AppState) -> Self {
+        state.verify_executor.clone()
+    }
+}
+
 #[tokio::main]
 async fn main() {
     tracing_subscriber::fmt::init();
@@ -16,13 +51,26 @@ async fn main() {
     let katas_dir = PathBuf::from("../katas");
     let katas = services::kata_loader::load_all_katas(&katas_dir)
         .expect("Failed to load katas");
-    let katas = Arc::new(katas);
+    let state = AppState {
+        katas: Arc::new(katas),
+        executor: Arc::new(Executor::new()),
+        verify_executor: Arc::new(Executor::new()),
+    };
 
     let api = Router::new()
         .route("/katas", get(routes::katas::list_katas))
         .route("/katas/{id}", get(routes::katas::get_kata))
+        .route("/katas/{id}/verify", post(routes::katas::verify_kata))
         .route("/playground/run", post(routes::playground::run_code))
-        .with_state(katas);
+        .route(
+            "/playground/run/stream",
+            post(routes::playground::run_code_stream),
+        )
+        .route(
+            "/playground/jobs/{id}",
+            get(routes::playground::job_status).delete(routes::playground::cancel_job),
+        )
+        .with_state(state);
 
     let app = Router::new()
         .nest("/api", api)
@@ -3,9 +3,49 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Deserialize)]
 pub struct ExecutionRequest {
     pub code: String,
+    /// Crate dependency lines (e.g. `serde = "1"`, or a bare crate name for
+    /// a `"*"` version) to build against. Empty for the plain `rustc
+    /// main.rs` path; non-empty switches execution to a generated Cargo
+    /// project built offline against the vendored registry.
+    #[serde(default)]
+    pub dependencies: Vec<String>,
 }
 
-#[derive(Debug, Serialize)]
+/// Which leg of the build/run pipeline a streamed chunk came from.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionPhase {
+    Compile,
+    Run,
+}
+
+/// Which pipe a streamed chunk came from.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamKind {
+    Stdout,
+    Stderr,
+}
+
+/// One event in the `/api/playground/run/stream` SSE feed. `Output` events
+/// arrive as compile/run output becomes available; exactly one terminal
+/// event (`Timeout` or `Done`) closes the stream.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ExecutionEvent {
+    Output {
+        phase: ExecutionPhase,
+        stream: StreamKind,
+        data: String,
+    },
+    Timeout,
+    Done {
+        success: bool,
+        execution_time_ms: u64,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct ExecutionResult {
     pub stdout: String,
     pub stderr: String,
@@ -13,6 +53,8 @@ pub struct ExecutionResult {
     pub execution_time_ms: u64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub diagnostics: Vec<Diagnostic>,
 }
 
 impl ExecutionResult {
@@ -23,6 +65,42 @@ impl ExecutionResult {
             success: false,
             execution_time_ms: 0,
             error: Some(msg),
+            diagnostics: Vec::new(),
         }
     }
 }
+
+/// Severity of a single rustc diagnostic, as reported by `--error-format=json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticLevel {
+    Error,
+    Warning,
+    Note,
+    Help,
+    Other,
+}
+
+/// A source range a diagnostic points at, with an optional label explaining
+/// what's wrong at that range (e.g. "expected `String`, found `&str`").
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticSpan {
+    pub line_start: u32,
+    pub line_end: u32,
+    pub column_start: u32,
+    pub column_end: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+/// One machine-readable compiler diagnostic, parsed from rustc's JSON error
+/// format so the frontend can underline exact spans instead of just
+/// displaying the raw `stderr` text.
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostic {
+    pub level: DiagnosticLevel,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    pub spans: Vec<DiagnosticSpan>,
+}
@@ -13,6 +13,20 @@ pub struct Kata {
     pub correct_code: String,
     pub explanation: String,
     pub compiler_error_interpretation: String,
+    pub dependencies: Vec<String>,
+    /// Hidden grading vectors parsed from the kata's `## Tests` section.
+    /// Never serialized to clients — `routes::katas::verify_kata` is the
+    /// only thing allowed to see expected output.
+    #[serde(skip)]
+    pub tests: Vec<TestVector>,
+}
+
+/// One hidden grading case for a kata: feed `stdin` to the submitted
+/// program and compare its trimmed stdout against `expected_stdout`.
+#[derive(Debug, Clone)]
+pub struct TestVector {
+    pub stdin: String,
+    pub expected_stdout: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -0,0 +1,18 @@
+use serde::Serialize;
+
+/// Result of grading a submission against a kata's hidden test vectors.
+#[derive(Debug, Serialize)]
+pub struct VerificationResult {
+    pub passed: u32,
+    pub total: u32,
+    pub failures: Vec<VerificationFailure>,
+}
+
+/// One failing test vector, with the input and the expected vs. actual
+/// output so the UI can show why it failed.
+#[derive(Debug, Serialize)]
+pub struct VerificationFailure {
+    pub input: String,
+    pub expected: String,
+    pub actual: String,
+}
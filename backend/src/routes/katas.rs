@@ -3,7 +3,16 @@ use axum::http::StatusCode;
 use axum::Json;
 use std::sync::Arc;
 
+use crate::models::execution::{ExecutionRequest, ExecutionResult};
 use crate::models::kata::{Kata, KataListResponse, KataSummary, PhaseGroup};
+use crate::models::verification::{VerificationFailure, VerificationResult};
+use crate::services::executor::Executor;
+use crate::services::sandbox;
+
+/// What `verify_kata` enqueues on the bounded executor: the same `Result`
+/// shape `sandbox::run_test_vectors` returns, so `rx.await` hands the route
+/// handler exactly what it would have gotten from calling it directly.
+pub type VerifyResult = Result<Vec<String>, ExecutionResult>;
 
 pub async fn list_katas(State(katas): State<Arc<Vec<Kata>>>) -> Json<KataListResponse> {
     let mut phases: Vec<PhaseGroup> = Vec::new();
@@ -45,3 +54,87 @@ pub async fn get_kata(
         .map(Json)
         .ok_or(StatusCode::NOT_FOUND)
 }
+
+/// Grades a submission against the kata's hidden test vectors: compiles
+/// once, runs the binary once per vector, and compares trimmed stdout.
+/// Enqueued on its own bounded `Executor` (separate from the playground's),
+/// so grading can't be used to spawn unbounded concurrent compiles either.
+pub async fn verify_kata(
+    State(katas): State<Arc<Vec<Kata>>>,
+    State(executor): State<Arc<Executor<VerifyResult>>>,
+    Path(id): Path<String>,
+    Json(req): Json<ExecutionRequest>,
+) -> Result<Json<VerificationResult>, StatusCode> {
+    let kata = katas
+        .iter()
+        .find(|k| k.id == id)
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    // Either side can declare dependencies: the kata's own frontmatter
+    // (a kata that's inherently about an external crate) or the submission
+    // itself (the learner pulling one in to solve it).
+    let dependencies: Vec<String> = kata
+        .dependencies
+        .iter()
+        .chain(req.dependencies.iter())
+        .cloned()
+        .collect();
+    let tests = kata.tests.clone();
+    let code = req.code;
+
+    let (_job_id, rx) = executor
+        .enqueue(async move { sandbox::run_test_vectors(&code, &dependencies, &tests).await })
+        .map_err(|_| StatusCode::TOO_MANY_REQUESTS)?;
+
+    let outputs = match rx.await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)? {
+        Ok(outputs) => outputs,
+        Err(compile_failure) => {
+            let total = kata.tests.len() as u32;
+            // This Err also fires on a runtime spawn error or the run
+            // timeout, not just a compile failure — those put their message
+            // in `error` and leave `stderr` empty, so fall back to it here
+            // rather than always reading `stderr`.
+            let actual = if compile_failure.stderr.is_empty() {
+                compile_failure
+                    .error
+                    .clone()
+                    .unwrap_or_else(|| compile_failure.stderr.clone())
+            } else {
+                compile_failure.stderr.clone()
+            };
+            return Ok(Json(VerificationResult {
+                passed: 0,
+                total,
+                failures: kata
+                    .tests
+                    .iter()
+                    .map(|t| VerificationFailure {
+                        input: t.stdin.clone(),
+                        expected: t.expected_stdout.clone(),
+                        actual: actual.clone(),
+                    })
+                    .collect(),
+            }));
+        }
+    };
+
+    let mut passed = 0;
+    let mut failures = Vec::new();
+    for (test, actual) in kata.tests.iter().zip(outputs) {
+        if actual == test.expected_stdout.trim() {
+            passed += 1;
+        } else {
+            failures.push(VerificationFailure {
+                input: test.stdin.clone(),
+                expected: test.expected_stdout.clone(),
+                actual,
+            });
+        }
+    }
+
+    Ok(Json(VerificationResult {
+        passed,
+        total: kata.tests.len() as u32,
+        failures,
+    }))
+}
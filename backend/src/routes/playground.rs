@@ -1,8 +1,100 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
 use axum::Json;
+use serde::Serialize;
+use std::convert::Infallible;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
 
-use crate::models::execution::{ExecutionRequest, ExecutionResult};
+use crate::models::execution::{ExecutionEvent, ExecutionRequest, ExecutionResult};
+use crate::services::executor::{Executor, JobStatus};
 use crate::services::sandbox;
 
-pub async fn run_code(Json(req): Json<ExecutionRequest>) -> Json<ExecutionResult> {
-    Json(sandbox::execute_rust_code(&req.code).await)
+/// Enqueues the submission on the bounded executor and waits for it to
+/// finish, so this endpoint's contract (one request in, one
+/// `ExecutionResult` out) is unchanged for existing clients. Answers 429
+/// if the executor's backlog is already full.
+pub async fn run_code(
+    State(executor): State<Arc<Executor<ExecutionResult>>>,
+    Json(req): Json<ExecutionRequest>,
+) -> Result<Json<ExecutionResult>, StatusCode> {
+    let (_job_id, rx) = executor
+        .enqueue(async move { sandbox::execute_rust_code(&req.code, &req.dependencies).await })
+        .map_err(|_| StatusCode::TOO_MANY_REQUESTS)?;
+
+    rx.await
+        .map(Json)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// Streams compile/run output incrementally instead of buffering the whole
+/// `ExecutionResult`, so long-running programs show progress as it happens.
+/// Also goes through the bounded executor, answering 429 if the backlog is
+/// full; the job id is sent as the first SSE event so the client can poll
+/// `GET /api/playground/jobs/{id}` or cancel it mid-flight.
+pub async fn run_code_stream(
+    State(executor): State<Arc<Executor<ExecutionResult>>>,
+    Json(req): Json<ExecutionRequest>,
+) -> Result<Sse<impl tokio_stream::Stream<Item = Result<Event, Infallible>>>, StatusCode> {
+    let (event_tx, event_rx) = mpsc::channel::<ExecutionEvent>(64);
+
+    let (job_id, _result_rx) = executor
+        .enqueue(async move {
+            sandbox::execute_rust_code_streaming(&req.code, &req.dependencies, event_tx).await
+        })
+        .map_err(|_| StatusCode::TOO_MANY_REQUESTS)?;
+
+    let job_started = futures_util::stream::once(async move {
+        Ok(Event::default()
+            .json_data(&JobStartedEvent { job_id })
+            .unwrap_or_else(|_| Event::default().data("{}")))
+    });
+
+    let output_events = ReceiverStream::new(event_rx).map(|event| {
+        Ok(Event::default()
+            .json_data(&event)
+            .unwrap_or_else(|_| Event::default().data("{}")))
+    });
+
+    Ok(Sse::new(job_started.chain(output_events)).keep_alive(KeepAlive::default()))
+}
+
+#[derive(Serialize)]
+struct JobStartedEvent {
+    job_id: String,
+}
+
+#[derive(Serialize)]
+struct JobStatusResponse {
+    status: JobStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<ExecutionResult>,
+}
+
+pub async fn job_status(
+    State(executor): State<Arc<Executor<ExecutionResult>>>,
+    Path(id): Path<String>,
+) -> Result<Json<JobStatusResponse>, StatusCode> {
+    let status = executor.status(&id).await.ok_or(StatusCode::NOT_FOUND)?;
+    let result = if status == JobStatus::Finished {
+        executor.result(&id).await
+    } else {
+        None
+    };
+
+    Ok(Json(JobStatusResponse { status, result }))
+}
+
+pub async fn cancel_job(
+    State(executor): State<Arc<Executor<ExecutionResult>>>,
+    Path(id): Path<String>,
+) -> StatusCode {
+    if executor.cancel(&id).await {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
 }
@@ -0,0 +1,244 @@
+use dashmap::DashMap;
+use serde::Serialize;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+use tokio::sync::{oneshot, Mutex, Semaphore};
+use tokio::time::Instant;
+use tokio::task::JoinHandle;
+
+pub type JobId = String;
+
+/// How many sandbox jobs (rustc/cargo/binary processes) may run at once.
+const MAX_CONCURRENT_JOBS: usize = 4;
+/// How many jobs may sit queued or running before new submissions are
+/// rejected with a 429 instead of piling up behind the semaphore.
+const MAX_QUEUE_DEPTH: usize = 32;
+/// How long a finished job's result stays in `jobs` before it's swept,
+/// bounding memory on a long-lived server. Long enough that a client
+/// polling `GET /jobs/{id}` right after completion still finds it.
+const FINISHED_JOB_TTL: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Finished,
+}
+
+/// Returned by `enqueue` when the backlog is already at `MAX_QUEUE_DEPTH`;
+/// callers should answer with HTTP 429.
+#[derive(Debug)]
+pub struct QueueFull;
+
+struct Job<T> {
+    status: Mutex<JobStatus>,
+    result: Mutex<Option<T>>,
+    handle: Mutex<Option<JoinHandle<()>>>,
+    /// Set once the job reaches `Finished`, so `sweep_expired` can decide
+    /// whether to evict it without needing an `.await` (it runs from the
+    /// sync `enqueue`), hence the plain `std::sync::Mutex` here instead of
+    /// `tokio::sync::Mutex` like the fields above.
+    finished_at: StdMutex<Option<Instant>>,
+}
+
+/// A `Semaphore`-bounded worker pool for sandbox executions. Every
+/// submission is tracked in `jobs` from the moment it's enqueued (status
+/// `Queued`) through execution (`Running`) to completion (`Finished`), so
+/// `GET /api/playground/jobs/{id}` can report on it and
+/// `DELETE /api/playground/jobs/{id}` can cancel it mid-flight.
+pub struct Executor<T> {
+    semaphore: Arc<Semaphore>,
+    queue_depth: Arc<AtomicUsize>,
+    jobs: DashMap<JobId, Arc<Job<T>>>,
+}
+
+impl<T: Clone + Send + 'static> Executor<T> {
+    pub fn new() -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(MAX_CONCURRENT_JOBS)),
+            queue_depth: Arc::new(AtomicUsize::new(0)),
+            jobs: DashMap::new(),
+        }
+    }
+
+    /// Current number of queued + running jobs, for surfacing backlog
+    /// depth to operators or clients.
+    pub fn queue_depth(&self) -> usize {
+        self.queue_depth.load(Ordering::SeqCst)
+    }
+
+    /// Registers `work`, returning its job id and a receiver for its final
+    /// result immediately — `work` itself only starts running once a
+    /// worker slot is free. Rejects with `QueueFull` once the backlog
+    /// exceeds `MAX_QUEUE_DEPTH`, so a burst of submissions degrades
+    /// gracefully instead of forking the host to death.
+    pub fn enqueue<F>(&self, work: F) -> Result<(JobId, oneshot::Receiver<T>), QueueFull>
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        self.sweep_expired();
+
+        if self.queue_depth() >= MAX_QUEUE_DEPTH {
+            return Err(QueueFull);
+        }
+        self.queue_depth.fetch_add(1, Ordering::SeqCst);
+
+        let id = new_job_id();
+        let job = Arc::new(Job {
+            status: Mutex::new(JobStatus::Queued),
+            result: Mutex::new(None),
+            handle: Mutex::new(None),
+            finished_at: StdMutex::new(None),
+        });
+        self.jobs.insert(id.clone(), job.clone());
+
+        let (tx, rx) = oneshot::channel();
+        let semaphore = self.semaphore.clone();
+        let queue_depth = self.queue_depth.clone();
+        let job_for_task = job.clone();
+        let handle = tokio::spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            *job_for_task.status.lock().await = JobStatus::Running;
+
+            let output = work.await;
+
+            *job_for_task.result.lock().await = Some(output.clone());
+            *job_for_task.status.lock().await = JobStatus::Finished;
+            *job_for_task.finished_at.lock().unwrap() = Some(Instant::now());
+            queue_depth.fetch_sub(1, Ordering::SeqCst);
+            let _ = tx.send(output);
+        });
+
+        // The task above never locks `handle`, and no caller has the id yet
+        // to `cancel` it, so the lock is always free here.
+        *job.handle.try_lock().expect("job handle uncontended") = Some(handle);
+
+        Ok((id, rx))
+    }
+
+    /// Evicts jobs that finished more than `FINISHED_JOB_TTL` ago, so
+    /// `jobs` doesn't grow without bound over the life of the process. Run
+    /// opportunistically on every `enqueue` rather than from a background
+    /// task — that's the only place new entries are added, so it's also
+    /// the only place growth needs to be capped.
+    fn sweep_expired(&self) {
+        self.jobs.retain(|_, job| {
+            match *job.finished_at.lock().unwrap() {
+                Some(finished_at) => finished_at.elapsed() < FINISHED_JOB_TTL,
+                None => true,
+            }
+        });
+    }
+
+    pub async fn status(&self, id: &str) -> Option<JobStatus> {
+        let job = self.jobs.get(id)?;
+        Some(*job.status.lock().await)
+    }
+
+    pub async fn result(&self, id: &str) -> Option<T> {
+        let job = self.jobs.get(id)?;
+        job.result.lock().await.clone()
+    }
+
+    /// Cancels a queued or running job: aborts its task (dropping any
+    /// sandbox-spawned `GroupChild` it holds kills that process's whole
+    /// group, not just the immediate pid — see
+    /// `services::sandbox::GroupChild`) and stops tracking it. Returns
+    /// `false` if the id is unknown.
+    pub async fn cancel(&self, id: &str) -> bool {
+        let Some((_, job)) = self.jobs.remove(id) else {
+            return false;
+        };
+
+        if let Some(handle) = job.handle.lock().await.take() {
+            handle.abort();
+        }
+        if *job.status.lock().await != JobStatus::Finished {
+            self.queue_depth.fetch_sub(1, Ordering::SeqCst);
+        }
+        true
+    }
+}
+
+fn new_job_id() -> JobId {
+    uuid::Uuid::new_v4().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::oneshot as tokio_oneshot;
+
+    #[tokio::test]
+    async fn enqueue_runs_to_completion_and_reports_status() {
+        let executor: Executor<u32> = Executor::new();
+
+        let (id, rx) = executor.enqueue(async { 42 }).unwrap();
+        assert_eq!(rx.await.unwrap(), 42);
+        assert_eq!(executor.status(&id).await, Some(JobStatus::Finished));
+        assert_eq!(executor.result(&id).await, Some(42));
+    }
+
+    #[tokio::test]
+    async fn queue_depth_tracks_in_flight_jobs_and_drops_on_completion() {
+        let executor: Executor<u32> = Executor::new();
+        let (gate_tx, gate_rx) = tokio_oneshot::channel::<()>();
+
+        let (_id, rx) = executor
+            .enqueue(async move {
+                let _ = gate_rx.await;
+                1
+            })
+            .unwrap();
+        assert_eq!(executor.queue_depth(), 1);
+
+        gate_tx.send(()).unwrap();
+        rx.await.unwrap();
+        assert_eq!(executor.queue_depth(), 0);
+    }
+
+    #[tokio::test]
+    async fn cancel_stops_tracking_a_queued_job_and_frees_its_queue_slot() {
+        let executor: Executor<u32> = Executor::new();
+        let (_gate_tx, gate_rx) = tokio_oneshot::channel::<()>();
+
+        let (id, _rx) = executor
+            .enqueue(async move {
+                let _ = gate_rx.await;
+                1
+            })
+            .unwrap();
+        assert_eq!(executor.queue_depth(), 1);
+
+        assert!(executor.cancel(&id).await);
+        assert_eq!(executor.queue_depth(), 0);
+        assert_eq!(executor.status(&id).await, None);
+    }
+
+    #[tokio::test]
+    async fn cancel_reports_false_for_an_unknown_id() {
+        let executor: Executor<u32> = Executor::new();
+        assert!(!executor.cancel("does-not-exist").await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn sweep_expired_evicts_finished_jobs_after_the_ttl() {
+        let executor: Executor<u32> = Executor::new();
+
+        let (id, rx) = executor.enqueue(async { 1 }).unwrap();
+        rx.await.unwrap();
+        assert_eq!(executor.status(&id).await, Some(JobStatus::Finished));
+
+        tokio::time::advance(FINISHED_JOB_TTL + Duration::from_secs(1)).await;
+
+        // sweep_expired only runs from enqueue, so a second submission is
+        // what actually triggers eviction of the now-stale first job.
+        let (_next_id, next_rx) = executor.enqueue(async { 2 }).unwrap();
+        next_rx.await.unwrap();
+
+        assert_eq!(executor.status(&id).await, None);
+    }
+}
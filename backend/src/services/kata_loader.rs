@@ -1,4 +1,4 @@
-use crate::models::kata::Kata;
+use crate::models::kata::{Kata, TestVector};
 use serde::Deserialize;
 use std::path::Path;
 
@@ -11,6 +11,8 @@ struct KataFrontmatter {
     title: String,
     #[serde(default)]
     hints: Vec<String>,
+    #[serde(default)]
+    dependencies: Vec<String>,
 }
 
 pub fn load_all_katas(katas_dir: &Path) -> Result<Vec<Kata>, Box<dyn std::error::Error>> {
@@ -74,6 +76,7 @@ fn parse_kata_file(path: &Path) -> Result<Kata, Box<dyn std::error::Error>> {
     let correct_code = extract_code_block(body, "Correct Code");
     let explanation = extract_section(body, "Explanation");
     let compiler_error = extract_section(body, "Compiler Error Interpretation");
+    let tests = extract_test_vectors(body);
 
     Ok(Kata {
         id: frontmatter.id,
@@ -87,6 +90,8 @@ fn parse_kata_file(path: &Path) -> Result<Kata, Box<dyn std::error::Error>> {
         correct_code,
         explanation,
         compiler_error_interpretation: compiler_error,
+        dependencies: frontmatter.dependencies,
+        tests,
     })
 }
 
@@ -120,3 +125,126 @@ fn extract_code_block(body: &str, heading: &str) -> String {
     let end = code_body.find("```").unwrap_or(code_body.len());
     code_body[..end].trim().to_string()
 }
+
+/// Parses the `## Tests` section into hidden grading vectors. Each vector
+/// is a pair of fenced code blocks tagged ```stdin``` and ```stdout```,
+/// paired up in the order they appear:
+///
+/// ```text
+/// ## Tests
+/// ```stdin
+/// 3
+/// ```
+/// ```stdout
+/// 6
+/// ```
+/// ```
+fn extract_test_vectors(body: &str) -> Vec<TestVector> {
+    let section = extract_section(body, "Tests");
+    let stdins = extract_tagged_blocks(&section, "stdin");
+    let stdouts = extract_tagged_blocks(&section, "stdout");
+
+    stdins
+        .into_iter()
+        .zip(stdouts)
+        .map(|(stdin, expected_stdout)| TestVector {
+            stdin,
+            expected_stdout,
+        })
+        .collect()
+}
+
+/// Finds every ` ```{tag} ... ``` ` fenced block in `section`, in order.
+fn extract_tagged_blocks(section: &str, tag: &str) -> Vec<String> {
+    let marker = format!("```{}", tag);
+    let mut blocks = Vec::new();
+    let mut rest = section;
+
+    while let Some(start) = rest.find(&marker) {
+        let after_marker = &rest[start + marker.len()..];
+        let content_start = after_marker.find('\n').map_or(0, |i| i + 1);
+        let content = &after_marker[content_start..];
+
+        let Some(end) = content.find("```") else {
+            break;
+        };
+
+        blocks.push(content[..end].trim().to_string());
+        rest = &content[end + 3..];
+    }
+
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_test_vectors_pairs_stdin_and_stdout_in_order() {
+        let body = "\
+## Tests
+```stdin
+3
+```
+```stdout
+6
+```
+```stdin
+4
+```
+```stdout
+8
+```
+";
+        let tests = extract_test_vectors(body);
+        assert_eq!(tests.len(), 2);
+        assert_eq!(tests[0].stdin, "3");
+        assert_eq!(tests[0].expected_stdout, "6");
+        assert_eq!(tests[1].stdin, "4");
+        assert_eq!(tests[1].expected_stdout, "8");
+    }
+
+    #[test]
+    fn extract_test_vectors_drops_an_unpaired_trailing_block() {
+        // More stdin blocks than stdout blocks: zip() truncates to the
+        // shorter side, silently dropping the last, incomplete vector
+        // rather than erroring.
+        let body = "\
+## Tests
+```stdin
+3
+```
+```stdout
+6
+```
+```stdin
+4
+```
+";
+        let tests = extract_test_vectors(body);
+        assert_eq!(tests.len(), 1);
+        assert_eq!(tests[0].stdin, "3");
+    }
+
+    #[test]
+    fn extract_test_vectors_empty_section_yields_no_vectors() {
+        let body = "## Explanation\nnothing here\n";
+        assert!(extract_test_vectors(body).is_empty());
+    }
+
+    #[test]
+    fn extract_tagged_blocks_ignores_an_unterminated_fence() {
+        // A dangling ```stdin with no closing ``` shouldn't panic or loop
+        // forever — it should just stop collecting.
+        let section = "```stdin\nunterminated";
+        assert!(extract_tagged_blocks(section, "stdin").is_empty());
+    }
+
+    #[test]
+    fn extract_tagged_blocks_does_not_match_other_tags() {
+        let section = "```stdout\n1\n```\n```stdin\n2\n```\n";
+        assert_eq!(extract_tagged_blocks(section, "stdin"), vec!["2"]);
+        assert_eq!(extract_tagged_blocks(section, "stdout"), vec!["1"]);
+    }
+}
@@ -1,75 +1,1221 @@
-use crate::models::execution::ExecutionResult;
+use crate::models::execution::{
+    Diagnostic, DiagnosticLevel, DiagnosticSpan, ExecutionEvent, ExecutionPhase, ExecutionResult,
+    StreamKind,
+};
+use crate::models::kata::TestVector;
+use serde::Deserialize;
+use std::process::Stdio;
 use std::time::Instant;
 use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc;
 use tokio::time::{timeout, Duration};
 
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+
 const COMPILE_TIMEOUT: Duration = Duration::from_secs(10);
 const RUN_TIMEOUT: Duration = Duration::from_secs(5);
+const CARGO_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Wraps a `tokio::process::Child` spawned into its own process group (via
+/// `process_group(0)` on Unix), so dropping it — including when
+/// `Executor::cancel` aborts the task that owns it — kills not just the
+/// immediate process but anything it spawned. This matters most for Cargo
+/// mode, where killing just `cargo` would orphan its own `rustc` and the
+/// built binary.
+struct GroupChild(tokio::process::Child);
+
+impl GroupChild {
+    fn spawn(cmd: &mut tokio::process::Command) -> std::io::Result<Self> {
+        #[cfg(unix)]
+        cmd.process_group(0);
+        Ok(Self(cmd.kill_on_drop(true).spawn()?))
+    }
+
+    async fn wait(&mut self) -> std::io::Result<std::process::ExitStatus> {
+        self.0.wait().await
+    }
+
+    fn take_stdout(&mut self) -> tokio::process::ChildStdout {
+        self.0.stdout.take().expect("piped stdout")
+    }
+
+    fn take_stderr(&mut self) -> tokio::process::ChildStderr {
+        self.0.stderr.take().expect("piped stderr")
+    }
+
+    fn take_stdin(&mut self) -> Option<tokio::process::ChildStdin> {
+        self.0.stdin.take()
+    }
+
+    /// Drains stdout/stderr concurrently with the wait, same as the stdlib's
+    /// own `Child::wait_with_output`, so a chatty child can't deadlock by
+    /// filling one pipe's buffer while we're blocked reading the other.
+    async fn wait_with_output(mut self) -> std::io::Result<std::process::Output> {
+        async fn read_to_end<R: tokio::io::AsyncRead + Unpin>(
+            io: Option<R>,
+        ) -> std::io::Result<Vec<u8>> {
+            let mut buf = Vec::new();
+            if let Some(mut io) = io {
+                io.read_to_end(&mut buf).await?;
+            }
+            Ok(buf)
+        }
+
+        let stdout = self.0.stdout.take();
+        let stderr = self.0.stderr.take();
+        let (status, stdout, stderr) =
+            tokio::try_join!(self.0.wait(), read_to_end(stdout), read_to_end(stderr))?;
+
+        Ok(std::process::Output {
+            status,
+            stdout,
+            stderr,
+        })
+    }
+
+    /// Kills the whole process group now, synchronously, rather than
+    /// waiting for this value to eventually drop — used on the timeout
+    /// paths, which need the child dead *before* draining its stream
+    /// readers below.
+    async fn kill(&mut self) {
+        kill_process_group(&self.0);
+        let _ = self.0.kill().await;
+    }
+}
 
-pub async fn execute_rust_code(code: &str) -> ExecutionResult {
+impl Drop for GroupChild {
+    fn drop(&mut self) {
+        kill_process_group(&self.0);
+    }
+}
+
+fn kill_process_group(_child: &tokio::process::Child) {
+    #[cfg(unix)]
+    if let Some(pid) = _child.id() {
+        // SAFETY: signalling a pid we just read off our own child. The
+        // negated pid targets the whole process group (set via
+        // `process_group(0)` at spawn) rather than just this one process.
+        unsafe {
+            libc::kill(-(pid as i32), libc::SIGKILL);
+        }
+    }
+}
+
+// Docker-backed execution: the host temp dir is bind-mounted read-only into
+// the container at a fixed path, so source/compile paths differ from the
+// host. CONTAINER_SOURCE is what we tell rustc to compile and what shows up
+// in its diagnostics; we rewrite it back to "main.rs" before returning.
+const DOCKER_IMAGE: &str = "rust:1.75-slim";
+const CONTAINER_WORKDIR: &str = "/work";
+const CONTAINER_SOURCE: &str = "/work/main.rs";
+const CONTAINER_BINARY: &str = "/tmp/sandbox-out/main";
+/// Cargo builds need more scratch space than a single-file rustc compile
+/// (target dir, registry cache), hence the bigger tmpfs than the plain
+/// rustc path (`execute_in_docker`) uses.
+const CONTAINER_CARGO_TMPFS_SIZE_MB: u32 = 512;
+const CONTAINER_VENDOR_DIR: &str = "/vendor";
+
+pub async fn execute_rust_code(code: &str, dependencies: &[String]) -> ExecutionResult {
+    if !dependencies.is_empty() {
+        return execute_cargo_project(code, dependencies).await;
+    }
+
+    if docker_enabled() && docker_available().await {
+        execute_in_docker(code).await
+    } else {
+        execute_direct(code).await
+    }
+}
+
+/// Whether the Docker-backed execution path is turned on via config.
+/// Off by default so existing deployments keep the direct-rustc behavior.
+fn docker_enabled() -> bool {
+    std::env::var("SANDBOX_USE_DOCKER")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+async fn docker_available() -> bool {
+    tokio::process::Command::new("docker")
+        .arg("info")
+        .output()
+        .await
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Compiles and runs `code` directly on the host with only timeouts for
+/// isolation. Used when Docker isolation is disabled or unavailable.
+async fn execute_direct(code: &str) -> ExecutionResult {
+    let start = Instant::now();
+
+    let (_tmp_dir, binary_path, diagnostics) = match compile_direct(code, start).await {
+        Ok(v) => v,
+        Err(result) => return result,
+    };
+
+    // Run the compiled binary with timeout
+    let mut run_cmd = tokio::process::Command::new(&binary_path);
+    run_cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    let run_child = match GroupChild::spawn(&mut run_cmd) {
+        Ok(c) => c,
+        Err(e) => return ExecutionResult::error(format!("Failed to run binary: {}", e)),
+    };
+    let run_result = timeout(RUN_TIMEOUT, run_child.wait_with_output()).await;
+
+    let run_output = match run_result {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => return ExecutionResult::error(format!("Failed to run binary: {}", e)),
+        Err(_) => return ExecutionResult::error("Execution timed out (5s limit)".into()),
+    };
+
+    ExecutionResult {
+        stdout: String::from_utf8_lossy(&run_output.stdout).to_string(),
+        stderr: String::from_utf8_lossy(&run_output.stderr).to_string(),
+        success: run_output.status.success(),
+        execution_time_ms: start.elapsed().as_millis() as u64,
+        error: None,
+        // Compilation succeeded, but may still have produced warnings.
+        diagnostics,
+    }
+}
+
+/// Writes `code` into a fresh temp dir and compiles it with `rustc`,
+/// returning the temp dir (kept alive so the binary isn't cleaned up out
+/// from under the caller), the compiled binary's path, and any diagnostics
+/// (including warnings on an otherwise successful compile). On a compile
+/// failure (or any earlier I/O error) returns the finished `ExecutionResult`
+/// directly so callers can just propagate it.
+async fn compile_direct(
+    code: &str,
+    start: Instant,
+) -> Result<(TempDir, std::path::PathBuf, Vec<Diagnostic>), ExecutionResult> {
+    let tmp_dir = TempDir::new()
+        .map_err(|e| ExecutionResult::error(format!("Failed to create temp dir: {}", e)))?;
+
+    let source_path = tmp_dir.path().join("main.rs");
+    let binary_path = tmp_dir.path().join("main");
+
+    tokio::fs::write(&source_path, code)
+        .await
+        .map_err(|e| ExecutionResult::error(format!("Failed to write source: {}", e)))?;
+
+    // Compile with timeout. --error-format=json gives us structured
+    // diagnostics (with spans) alongside the human-readable "rendered" text
+    // we fall back to for the plain stderr field.
+    let mut compile_cmd = tokio::process::Command::new("rustc");
+    compile_cmd
+        .arg("--edition")
+        .arg("2021")
+        .arg("--error-format=json")
+        .arg(&source_path)
+        .arg("-o")
+        .arg(&binary_path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let compile_child = match GroupChild::spawn(&mut compile_cmd) {
+        Ok(c) => c,
+        Err(e) => return Err(ExecutionResult::error(format!("Failed to run rustc: {}", e))),
+    };
+    let compile_result = timeout(COMPILE_TIMEOUT, compile_child.wait_with_output()).await;
+
+    let compile_output = match compile_result {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => return Err(ExecutionResult::error(format!("Failed to run rustc: {}", e))),
+        Err(_) => {
+            return Err(ExecutionResult::error(
+                "Compilation timed out (10s limit)".into(),
+            ))
+        }
+    };
+
+    let compile_stderr = String::from_utf8_lossy(&compile_output.stderr);
+    let diagnostics = parse_rustc_json_diagnostics(&compile_stderr);
+    let rendered_stderr = render_diagnostics_text(&compile_stderr, &diagnostics);
+
+    if !compile_output.status.success() {
+        return Err(ExecutionResult {
+            stdout: String::new(),
+            stderr: rendered_stderr,
+            success: false,
+            execution_time_ms: start.elapsed().as_millis() as u64,
+            error: None,
+            diagnostics,
+        });
+    }
+
+    Ok((tmp_dir, binary_path, diagnostics))
+}
+
+/// Compiles `code` once, then runs the resulting binary once per test
+/// vector, feeding each vector's `stdin` to the process and collecting its
+/// trimmed stdout. Builds as a Cargo project instead of a plain `rustc
+/// main.rs` compile when `dependencies` is non-empty, so a kata whose
+/// frontmatter (or the submission itself) declares crates still grades
+/// correctly. Returns the compile failure as `Err` if compilation itself
+/// fails; `routes::katas::verify_kata` is responsible for comparing the
+/// returned outputs against each vector's `expected_stdout`.
+///
+/// Routes through the same Docker isolation as `execute_rust_code` when
+/// it's enabled, rather than always compiling and running directly on the
+/// host — grading a submission is no less risky than running it in the
+/// playground.
+pub async fn run_test_vectors(
+    code: &str,
+    dependencies: &[String],
+    tests: &[TestVector],
+) -> Result<Vec<String>, ExecutionResult> {
+    if docker_enabled() && docker_available().await {
+        run_test_vectors_in_docker(code, dependencies, tests).await
+    } else {
+        run_test_vectors_direct(code, dependencies, tests).await
+    }
+}
+
+async fn run_test_vectors_direct(
+    code: &str,
+    dependencies: &[String],
+    tests: &[TestVector],
+) -> Result<Vec<String>, ExecutionResult> {
+    let start = Instant::now();
+    let (_tmp_dir, binary_path, _diagnostics) =
+        compile_with_dependencies(code, dependencies, start).await?;
+
+    let mut outputs = Vec::with_capacity(tests.len());
+    for test in tests {
+        let mut cmd = tokio::process::Command::new(&binary_path);
+        cmd.stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        let mut child = GroupChild::spawn(&mut cmd)
+            .map_err(|e| ExecutionResult::error(format!("Failed to run binary: {}", e)))?;
+
+        if let Some(mut stdin) = child.take_stdin() {
+            let _ = stdin.write_all(test.stdin.as_bytes()).await;
+        }
+
+        let output = match timeout(RUN_TIMEOUT, child.wait_with_output()).await {
+            Ok(Ok(output)) => output,
+            Ok(Err(e)) => {
+                return Err(ExecutionResult::error(format!(
+                    "Failed to run binary: {}",
+                    e
+                )))
+            }
+            Err(_) => return Err(ExecutionResult::error("Execution timed out (5s limit)".into())),
+        };
+
+        outputs.push(String::from_utf8_lossy(&output.stdout).trim().to_string());
+    }
+
+    Ok(outputs)
+}
+
+/// Boundary the container script prints between each test vector's stdout,
+/// so the host can split one combined capture back into per-vector output
+/// without spawning a container per vector. Chosen to be vanishingly
+/// unlikely to appear in a submission's own output.
+const TEST_OUTPUT_DELIMITER: &str = "\u{1}--kata-test-boundary--\u{1}";
+const CONTAINER_STDIN_DIR: &str = "/work/stdins";
+
+/// Same as `run_test_vectors_direct`, but builds and runs inside the same
+/// locked-down `docker run` container the playground uses. Each test
+/// vector's stdin is written to a file under the bind mount, and the
+/// container script runs the built binary once per file, separating their
+/// stdout with `TEST_OUTPUT_DELIMITER` — one container invocation per
+/// submission rather than one per test vector.
+async fn run_test_vectors_in_docker(
+    code: &str,
+    dependencies: &[String],
+    tests: &[TestVector],
+) -> Result<Vec<String>, ExecutionResult> {
+    let start = Instant::now();
+
+    let tmp_dir = TempDir::new()
+        .map_err(|e| ExecutionResult::error(format!("Failed to create temp dir: {}", e)))?;
+
+    let vendor_dir = vendor_registry_dir();
+    let container_vendor_dir = vendor_dir
+        .as_ref()
+        .map(|_| std::path::Path::new(CONTAINER_VENDOR_DIR));
+    write_cargo_project(tmp_dir.path(), code, dependencies, container_vendor_dir)
+        .await
+        .map_err(ExecutionResult::error)?;
+
+    let stdin_dir = tmp_dir.path().join("stdins");
+    tokio::fs::create_dir_all(&stdin_dir)
+        .await
+        .map_err(|e| ExecutionResult::error(format!("Failed to create temp dir: {}", e)))?;
+    for (i, test) in tests.iter().enumerate() {
+        tokio::fs::write(stdin_dir.join(format!("{}.txt", i)), &test.stdin)
+            .await
+            .map_err(|e| ExecutionResult::error(format!("Failed to write test input: {}", e)))?;
+    }
+
+    let bind_mount = format!("{}:{}:ro", tmp_dir.path().display(), CONTAINER_WORKDIR);
+    let mut cmd = docker_command(&bind_mount, CONTAINER_CARGO_TMPFS_SIZE_MB);
+    if let Some(vendor_dir) = &vendor_dir {
+        cmd.arg("-v")
+            .arg(format!("{}:{}:ro", vendor_dir.display(), CONTAINER_VENDOR_DIR));
+    }
+
+    // Build once; a non-zero build exit is our compile-failure marker (97),
+    // same convention as execute_in_docker.
+    // On success, run the binary once per stdin file in index order and
+    // print the delimiter after each so the host can split the combined
+    // stdout back into per-vector outputs.
+    let script = format!(
+        "cp -r {work} /tmp/project && cd /tmp/project && \
+         CARGO_HOME=/tmp/cargo-home CARGO_TARGET_DIR=/tmp/target cargo build --offline --quiet 2>/tmp/build.log; \
+         rc=$?; if [ $rc -ne 0 ]; then cat /tmp/build.log 1>&2; exit 97; fi; \
+         bin=$(find /tmp/target/debug -maxdepth 1 -type f -executable | head -n1); \
+         i=0; while [ $i -lt {count} ]; do \"$bin\" < {stdin_dir}/$i.txt; printf '%s' '{delim}'; i=$((i + 1)); done",
+        work = CONTAINER_WORKDIR,
+        count = tests.len(),
+        stdin_dir = CONTAINER_STDIN_DIR,
+        delim = TEST_OUTPUT_DELIMITER,
+    );
+
+    let result = timeout(
+        CARGO_TIMEOUT + RUN_TIMEOUT * (tests.len().max(1) as u32),
+        cmd.arg(DOCKER_IMAGE).arg("sh").arg("-c").arg(&script).output(),
+    )
+    .await;
+
+    let output = match result {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => return Err(ExecutionResult::error(format!("Failed to run docker: {}", e))),
+        Err(_) => return Err(ExecutionResult::error("Build/execution timed out".into())),
+    };
+
+    if output.status.code() == Some(97) {
+        return Err(ExecutionResult {
+            stdout: String::new(),
+            stderr: rewrite_container_paths(&String::from_utf8_lossy(&output.stderr)),
+            success: false,
+            execution_time_ms: start.elapsed().as_millis() as u64,
+            error: None,
+            diagnostics: Vec::new(),
+        });
+    }
+
+    let raw_stdout = String::from_utf8_lossy(&output.stdout);
+    let mut outputs: Vec<String> = raw_stdout
+        .split(TEST_OUTPUT_DELIMITER)
+        .map(|s| s.trim().to_string())
+        .collect();
+    outputs.pop(); // trailing segment after the last delimiter is always empty
+
+    Ok(outputs)
+}
+
+/// Builds and runs `code` as a real Cargo project so it can pull in
+/// external crates, instead of the single-file `rustc main.rs` path. The
+/// project is built `--offline` against a pre-populated vendored registry
+/// (configured via `CARGO_VENDOR_DIR`), so it works without outbound
+/// network access.
+async fn execute_cargo_project(code: &str, dependencies: &[String]) -> ExecutionResult {
+    if docker_enabled() && docker_available().await {
+        execute_cargo_project_in_docker(code, dependencies).await
+    } else {
+        execute_cargo_project_direct(code, dependencies).await
+    }
+}
+
+/// Builds and runs `code` as a Cargo project directly on the host with only
+/// timeouts for isolation. Used when Docker isolation is disabled or
+/// unavailable — same caveat as `execute_direct`.
+async fn execute_cargo_project_direct(code: &str, dependencies: &[String]) -> ExecutionResult {
     let start = Instant::now();
 
-    // Create isolated temp directory (auto-cleans on drop)
     let tmp_dir = match TempDir::new() {
         Ok(d) => d,
         Err(e) => return ExecutionResult::error(format!("Failed to create temp dir: {}", e)),
     };
 
-    let source_path = tmp_dir.path().join("main.rs");
-    let binary_path = tmp_dir.path().join("main");
+    if let Err(e) =
+        write_cargo_project(tmp_dir.path(), code, dependencies, vendor_registry_dir().as_deref())
+            .await
+    {
+        return ExecutionResult::error(e);
+    }
+
+    let mut cargo_cmd = tokio::process::Command::new("cargo");
+    cargo_cmd
+        .arg("run")
+        .arg("--offline")
+        .arg("--quiet")
+        .current_dir(tmp_dir.path())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let cargo_child = match GroupChild::spawn(&mut cargo_cmd) {
+        Ok(c) => c,
+        Err(e) => return ExecutionResult::error(format!("Failed to run cargo: {}", e)),
+    };
+    let result = timeout(CARGO_TIMEOUT, cargo_child.wait_with_output()).await;
+
+    let output = match result {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => return ExecutionResult::error(format!("Failed to run cargo: {}", e)),
+        Err(_) => return ExecutionResult::error("Build/execution timed out (30s limit)".into()),
+    };
+
+    ExecutionResult {
+        stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+        stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        success: output.status.success(),
+        execution_time_ms: start.elapsed().as_millis() as u64,
+        error: None,
+        diagnostics: Vec::new(),
+    }
+}
+
+/// Same as `execute_cargo_project_direct`, but builds and runs inside the
+/// same locked-down `docker run` container `execute_in_docker` uses, so a
+/// submission can't opt out of sandboxing just by declaring a dependency.
+/// The project is bind-mounted read-only, same as the single-file path, but
+/// Cargo needs to write `Cargo.lock`/build artifacts alongside the source —
+/// so the container script copies it into its writable tmpfs before
+/// building rather than building in place.
+async fn execute_cargo_project_in_docker(code: &str, dependencies: &[String]) -> ExecutionResult {
+    let start = Instant::now();
+
+    let tmp_dir = match TempDir::new() {
+        Ok(d) => d,
+        Err(e) => return ExecutionResult::error(format!("Failed to create temp dir: {}", e)),
+    };
 
-    // Write source file
+    let vendor_dir = vendor_registry_dir();
+    let container_vendor_dir = vendor_dir
+        .as_ref()
+        .map(|_| std::path::Path::new(CONTAINER_VENDOR_DIR));
+    if let Err(e) = write_cargo_project(tmp_dir.path(), code, dependencies, container_vendor_dir).await
+    {
+        return ExecutionResult::error(e);
+    }
+
+    let bind_mount = format!("{}:{}:ro", tmp_dir.path().display(), CONTAINER_WORKDIR);
+    let mut cmd = docker_command(&bind_mount, CONTAINER_CARGO_TMPFS_SIZE_MB);
+    if let Some(vendor_dir) = &vendor_dir {
+        cmd.arg("-v")
+            .arg(format!("{}:{}:ro", vendor_dir.display(), CONTAINER_VENDOR_DIR));
+    }
+
+    let script = format!(
+        "cp -r {work} /tmp/project && cd /tmp/project && \
+         CARGO_HOME=/tmp/cargo-home CARGO_TARGET_DIR=/tmp/target cargo run --offline --quiet",
+        work = CONTAINER_WORKDIR
+    );
+
+    let result = timeout(
+        CARGO_TIMEOUT,
+        cmd.arg(DOCKER_IMAGE).arg("sh").arg("-c").arg(&script).output(),
+    )
+    .await;
+
+    let output = match result {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => return ExecutionResult::error(format!("Failed to run docker: {}", e)),
+        Err(_) => return ExecutionResult::error("Build/execution timed out (30s limit)".into()),
+    };
+
+    ExecutionResult {
+        stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+        stderr: rewrite_container_paths(&String::from_utf8_lossy(&output.stderr)),
+        success: output.status.success(),
+        execution_time_ms: start.elapsed().as_millis() as u64,
+        error: None,
+        // Same limitation as execute_cargo_project_direct: Cargo's own
+        // --message-format=json uses a different envelope than rustc's
+        // --error-format=json, so it isn't parsed here.
+        diagnostics: Vec::new(),
+    }
+}
+
+/// Lays out a Cargo project in `dir`: `src/main.rs` holding `code`, a
+/// `Cargo.toml` built from `dependencies`, and (if `vendor_dir` is given) a
+/// `.cargo/config.toml` pointing at the vendored registry there. `vendor_dir`
+/// is passed in rather than resolved internally because the host-direct and
+/// Docker paths need different paths to the same vendored registry — the
+/// container only sees it at `CONTAINER_VENDOR_DIR`. Shared by
+/// `execute_cargo_project_direct`, `execute_cargo_project_in_docker`, and
+/// `compile_with_dependencies`.
+async fn write_cargo_project(
+    dir: &std::path::Path,
+    code: &str,
+    dependencies: &[String],
+    vendor_dir: Option<&std::path::Path>,
+) -> Result<(), String> {
+    let src_dir = dir.join("src");
+    tokio::fs::create_dir_all(&src_dir)
+        .await
+        .map_err(|e| format!("Failed to create project layout: {}", e))?;
+    tokio::fs::write(src_dir.join("main.rs"), code)
+        .await
+        .map_err(|e| format!("Failed to write source: {}", e))?;
+    tokio::fs::write(dir.join("Cargo.toml"), cargo_manifest(dependencies))
+        .await
+        .map_err(|e| format!("Failed to write Cargo.toml: {}", e))?;
+
+    if let Some(vendor_dir) = vendor_dir {
+        let cargo_dir = dir.join(".cargo");
+        if tokio::fs::create_dir_all(&cargo_dir).await.is_ok() {
+            let _ = tokio::fs::write(
+                cargo_dir.join("config.toml"),
+                vendored_source_config(vendor_dir),
+            )
+            .await;
+        }
+    }
+
+    Ok(())
+}
+
+/// Like `compile_direct`, but builds `code` as a Cargo project when
+/// `dependencies` is non-empty instead of a plain `rustc main.rs` compile,
+/// so hidden-test-case grading (`run_test_vectors`) also picks up crates a
+/// kata's own frontmatter or the submission declares. Diagnostics are
+/// always empty on this path, same as `execute_cargo_project` — Cargo's
+/// output isn't run through `--error-format=json`.
+async fn compile_with_dependencies(
+    code: &str,
+    dependencies: &[String],
+    start: Instant,
+) -> Result<(TempDir, std::path::PathBuf, Vec<Diagnostic>), ExecutionResult> {
+    if dependencies.is_empty() {
+        return compile_direct(code, start).await;
+    }
+
+    let tmp_dir = TempDir::new()
+        .map_err(|e| ExecutionResult::error(format!("Failed to create temp dir: {}", e)))?;
+
+    write_cargo_project(tmp_dir.path(), code, dependencies, vendor_registry_dir().as_deref())
+        .await
+        .map_err(ExecutionResult::error)?;
+
+    let mut build_cmd = tokio::process::Command::new("cargo");
+    build_cmd
+        .arg("build")
+        .arg("--offline")
+        .arg("--quiet")
+        .current_dir(tmp_dir.path())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let build_child = GroupChild::spawn(&mut build_cmd)
+        .map_err(|e| ExecutionResult::error(format!("Failed to run cargo: {}", e)))?;
+    let build_result = timeout(CARGO_TIMEOUT, build_child.wait_with_output()).await;
+
+    let output = match build_result {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => return Err(ExecutionResult::error(format!("Failed to run cargo: {}", e))),
+        Err(_) => {
+            return Err(ExecutionResult::error(
+                "Build timed out (30s limit)".into(),
+            ))
+        }
+    };
+
+    if !output.status.success() {
+        return Err(ExecutionResult {
+            stdout: String::new(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            success: false,
+            execution_time_ms: start.elapsed().as_millis() as u64,
+            error: None,
+            diagnostics: Vec::new(),
+        });
+    }
+
+    let binary_path = tmp_dir.path().join("target").join("debug").join("sandbox");
+    Ok((tmp_dir, binary_path, Vec::new()))
+}
+
+/// Renders a `Cargo.toml` for the sandboxed project. Each dependency entry
+/// is either a bare crate name (pinned to `"*"`) or a full TOML line like
+/// `serde = "1"` when the caller wants a specific version/features.
+fn cargo_manifest(dependencies: &[String]) -> String {
+    let mut manifest = String::from(
+        "[package]\nname = \"sandbox\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\n",
+    );
+    for dep in dependencies {
+        if dep.contains('=') {
+            manifest.push_str(dep);
+        } else {
+            manifest.push_str(&format!("{} = \"*\"", dep));
+        }
+        manifest.push('\n');
+    }
+    manifest
+}
+
+fn vendor_registry_dir() -> Option<std::path::PathBuf> {
+    std::env::var("CARGO_VENDOR_DIR").ok().map(std::path::PathBuf::from)
+}
+
+/// `.cargo/config.toml` that redirects crates.io to the vendored directory,
+/// the same trick `cargo vendor` prints after populating a vendor cache.
+fn vendored_source_config(vendor_dir: &std::path::Path) -> String {
+    format!(
+        "[source.crates-io]\nreplace-with = \"vendored-sources\"\n\n[source.vendored-sources]\ndirectory = \"{}\"\n",
+        vendor_dir.display()
+    )
+}
+
+/// Compiles and runs `code` inside a locked-down `docker run` container:
+/// no network, a read-only root filesystem (with a small writable tmpfs for
+/// the compiled binary), dropped capabilities, and memory/pids caps. The
+/// host temp dir is bind-mounted read-only so the submitted source can't be
+/// tampered with from inside the container.
+async fn execute_in_docker(code: &str) -> ExecutionResult {
+    let start = Instant::now();
+
+    let tmp_dir = match TempDir::new() {
+        Ok(d) => d,
+        Err(e) => return ExecutionResult::error(format!("Failed to create temp dir: {}", e)),
+    };
+
+    let source_path = tmp_dir.path().join("main.rs");
     if let Err(e) = tokio::fs::write(&source_path, code).await {
         return ExecutionResult::error(format!("Failed to write source: {}", e));
     }
 
-    // Compile with timeout
-    let compile_result = timeout(
-        COMPILE_TIMEOUT,
-        tokio::process::Command::new("rustc")
-            .arg("--edition")
-            .arg("2021")
-            .arg(&source_path)
-            .arg("-o")
-            .arg(&binary_path)
+    let bind_mount = format!("{}:{}:ro", tmp_dir.path().display(), CONTAINER_WORKDIR);
+    let binary_dir = std::path::Path::new(CONTAINER_BINARY)
+        .parent()
+        .expect("CONTAINER_BINARY has a parent dir");
+
+    // Compile and run inside a single container invocation: the root
+    // filesystem is read-only so the compiled binary can only live in the
+    // tmpfs of the container that produced it, and that container is gone
+    // once `docker run` returns. A dedicated exit code (97) lets us tell a
+    // compile error apart from the program's own output and exit codes.
+    // rustc's output goes to a log that is printed only on a compile
+    // failure, so warnings never mix into the program's stdout.
+    // --error-format=json mirrors the direct-rustc path so a failed compile's
+    // diagnostics are structured here too, not just when Docker is off.
+    let script = format!(
+        "mkdir -p {dir} && rustc --edition 2021 --error-format=json {src} -o {bin} 2>{dir}/rustc.log 1>/dev/null; \
+         rc=$?; if [ $rc -ne 0 ]; then cat {dir}/rustc.log; exit 97; fi; exec {bin}",
+        dir = binary_dir.display(),
+        src = CONTAINER_SOURCE,
+        bin = CONTAINER_BINARY
+    );
+
+    let compile_and_run_result = timeout(
+        COMPILE_TIMEOUT + RUN_TIMEOUT,
+        docker_command(&bind_mount, 64)
+            .arg(DOCKER_IMAGE)
+            .arg("sh")
+            .arg("-c")
+            .arg(&script)
             .output(),
     )
     .await;
 
-    let compile_output = match compile_result {
+    let output = match compile_and_run_result {
         Ok(Ok(output)) => output,
-        Ok(Err(e)) => return ExecutionResult::error(format!("Failed to run rustc: {}", e)),
-        Err(_) => return ExecutionResult::error("Compilation timed out (10s limit)".into()),
+        Ok(Err(e)) => return ExecutionResult::error(format!("Failed to run docker: {}", e)),
+        Err(_) => return ExecutionResult::error("Execution timed out".into()),
     };
 
-    if !compile_output.status.success() {
+    // Exit code 97 is our compile-failure marker (chosen to avoid colliding
+    // with the user program's own exit codes); on that path stdout carries
+    // the rustc diagnostics because the script prints rustc's log there,
+    // in the same --error-format=json shape the direct-rustc path parses.
+    // A successful compile's warnings are not returned as diagnostics.
+    if output.status.code() == Some(97) {
+        let raw_stderr = String::from_utf8_lossy(&output.stdout);
+        let diagnostics = parse_rustc_json_diagnostics(&raw_stderr);
+        let rendered_stderr = rewrite_container_paths(&render_diagnostics_text(&raw_stderr, &diagnostics));
         return ExecutionResult {
             stdout: String::new(),
-            stderr: String::from_utf8_lossy(&compile_output.stderr).to_string(),
+            stderr: rendered_stderr,
             success: false,
             execution_time_ms: start.elapsed().as_millis() as u64,
             error: None,
+            diagnostics,
         };
     }
 
-    // Run the compiled binary with timeout
-    let run_result = timeout(
-        RUN_TIMEOUT,
-        tokio::process::Command::new(&binary_path).output(),
-    )
-    .await;
+    ExecutionResult {
+        stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+        stderr: rewrite_container_paths(&String::from_utf8_lossy(&output.stderr)),
+        success: output.status.success(),
+        execution_time_ms: start.elapsed().as_millis() as u64,
+        error: None,
+        diagnostics: Vec::new(),
+    }
+}
 
-    let run_output = match run_result {
-        Ok(Ok(output)) => output,
-        Ok(Err(e)) => return ExecutionResult::error(format!("Failed to run binary: {}", e)),
-        Err(_) => return ExecutionResult::error("Execution timed out (5s limit)".into()),
+/// Builds the `docker run` invocation shared by the compile and run steps:
+/// no network, read-only root, dropped capabilities, and resource caps.
+/// `tmpfs_size_mb` sizes the writable `/tmp`, and the memory cap is the
+/// larger of it and 256 MB — Cargo builds need a bigger scratch area than a
+/// plain rustc compile, hence it's a parameter rather than baked in.
+fn docker_command(bind_mount: &str, tmpfs_size_mb: u32) -> tokio::process::Command {
+    let mut cmd = tokio::process::Command::new("docker");
+    cmd.arg("run")
+        .arg("--rm")
+        .arg("--network")
+        .arg("none")
+        .arg("--read-only")
+        .arg("--tmpfs")
+        .arg(format!("/tmp:rw,size={}m", tmpfs_size_mb))
+        .arg("--memory")
+        .arg(format!("{}m", tmpfs_size_mb.max(256)))
+        .arg("--pids-limit")
+        .arg("64")
+        .arg("--cap-drop")
+        .arg("ALL")
+        .arg("-v")
+        .arg(bind_mount);
+    cmd
+}
+
+/// Maps the in-container source path back to the bare filename the user
+/// submitted, so compiler errors don't leak the container mount layout.
+fn rewrite_container_paths(output: &str) -> String {
+    output.replace(CONTAINER_SOURCE, "main.rs")
+}
+
+/// Direct-rustc compile-and-run, streaming output as it's produced instead
+/// of buffering it. Each line of compile/run stdout and stderr is forwarded
+/// as an `ExecutionEvent::Output` as soon as it's read; a single terminal
+/// event (`Timeout` or `Done`) is sent last to close out the stream. The
+/// receiver closing early (e.g. the client disconnected) just makes the
+/// `send` calls below no-ops.
+///
+/// Also returns an `ExecutionResult` mirroring the terminal event, with
+/// `stdout`/`stderr` left empty on the direct-rustc path (that content
+/// already went out over the event stream; the Cargo-project branch returns
+/// its buffered result as is) — this lets `services::executor` record a finished job's
+/// status for callers polling `GET /api/playground/jobs/{id}` instead of
+/// watching the SSE stream.
+pub async fn execute_rust_code_streaming(
+    code: &str,
+    dependencies: &[String],
+    tx: mpsc::Sender<ExecutionEvent>,
+) -> ExecutionResult {
+    if !dependencies.is_empty() {
+        // Cargo's own output isn't cleanly separable into compile/run
+        // phases line-by-line, so the Cargo-project mode streams as a
+        // single buffered chunk rather than incrementally.
+        let result = execute_cargo_project(code, dependencies).await;
+        if !result.stdout.is_empty() {
+            let _ = tx
+                .send(ExecutionEvent::Output {
+                    phase: ExecutionPhase::Run,
+                    stream: StreamKind::Stdout,
+                    data: result.stdout.clone(),
+                })
+                .await;
+        }
+        if !result.stderr.is_empty() {
+            let _ = tx
+                .send(ExecutionEvent::Output {
+                    phase: ExecutionPhase::Run,
+                    stream: StreamKind::Stderr,
+                    data: result.stderr.clone(),
+                })
+                .await;
+        }
+        let _ = tx
+            .send(ExecutionEvent::Done {
+                success: result.success,
+                execution_time_ms: result.execution_time_ms,
+            })
+            .await;
+        return result;
+    }
+
+    let start = Instant::now();
+
+    let tmp_dir = match TempDir::new() {
+        Ok(d) => d,
+        Err(_) => {
+            let execution_time_ms = start.elapsed().as_millis() as u64;
+            let _ = tx
+                .send(ExecutionEvent::Done {
+                    success: false,
+                    execution_time_ms,
+                })
+                .await;
+            return failed_stream_result(execution_time_ms);
+        }
     };
 
+    let source_path = tmp_dir.path().join("main.rs");
+    let binary_path = tmp_dir.path().join("main");
+
+    if tokio::fs::write(&source_path, code).await.is_err() {
+        let execution_time_ms = start.elapsed().as_millis() as u64;
+        let _ = tx
+            .send(ExecutionEvent::Done {
+                success: false,
+                execution_time_ms,
+            })
+            .await;
+        return failed_stream_result(execution_time_ms);
+    }
+
+    let mut compile_cmd = tokio::process::Command::new("rustc");
+    compile_cmd
+        .arg("--edition")
+        .arg("2021")
+        .arg(&source_path)
+        .arg("-o")
+        .arg(&binary_path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let compile_child = GroupChild::spawn(&mut compile_cmd);
+
+    let mut compile_child = match compile_child {
+        Ok(c) => c,
+        Err(_) => {
+            let execution_time_ms = start.elapsed().as_millis() as u64;
+            let _ = tx
+                .send(ExecutionEvent::Done {
+                    success: false,
+                    execution_time_ms,
+                })
+                .await;
+            return failed_stream_result(execution_time_ms);
+        }
+    };
+
+    let compile_stdout = compile_child.take_stdout();
+    let compile_stderr = compile_child.take_stderr();
+    let stream_tasks = spawn_stream_tasks(
+        compile_stdout,
+        compile_stderr,
+        ExecutionPhase::Compile,
+        tx.clone(),
+    );
+
+    let compile_status = timeout(COMPILE_TIMEOUT, compile_child.wait()).await;
+
+    let compile_status = match compile_status {
+        Ok(Ok(status)) => status,
+        Ok(Err(_)) | Err(_) => {
+            // Kill before waiting on the readers: a timed-out child is
+            // still alive and holding its stdout/stderr write ends open,
+            // so awaiting the reader tasks first would block forever
+            // waiting for EOF that never comes. This kills the whole
+            // process group, not just `rustc` itself.
+            compile_child.kill().await;
+            let _ = stream_tasks.0.await;
+            let _ = stream_tasks.1.await;
+            let _ = tx.send(ExecutionEvent::Timeout).await;
+            return failed_stream_result(start.elapsed().as_millis() as u64);
+        }
+    };
+
+    let _ = stream_tasks.0.await;
+    let _ = stream_tasks.1.await;
+
+    if !compile_status.success() {
+        let execution_time_ms = start.elapsed().as_millis() as u64;
+        let _ = tx
+            .send(ExecutionEvent::Done {
+                success: false,
+                execution_time_ms,
+            })
+            .await;
+        return failed_stream_result(execution_time_ms);
+    }
+
+    let mut run_cmd = tokio::process::Command::new(&binary_path);
+    run_cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    let run_child = GroupChild::spawn(&mut run_cmd);
+
+    let mut run_child = match run_child {
+        Ok(c) => c,
+        Err(_) => {
+            let execution_time_ms = start.elapsed().as_millis() as u64;
+            let _ = tx
+                .send(ExecutionEvent::Done {
+                    success: false,
+                    execution_time_ms,
+                })
+                .await;
+            return failed_stream_result(execution_time_ms);
+        }
+    };
+
+    let run_stdout = run_child.take_stdout();
+    let run_stderr = run_child.take_stderr();
+    let stream_tasks = spawn_stream_tasks(run_stdout, run_stderr, ExecutionPhase::Run, tx.clone());
+
+    let run_status = timeout(RUN_TIMEOUT, run_child.wait()).await;
+
+    let run_status = match run_status {
+        Ok(Ok(status)) => status,
+        Ok(Err(_)) | Err(_) => {
+            // Same ordering fix as the compile phase above: kill first,
+            // then drain the readers, so a hung program can't wedge this
+            // task (and the `Executor` permit it's holding) forever. This
+            // kills the whole process group, not just the immediate pid.
+            run_child.kill().await;
+            let _ = stream_tasks.0.await;
+            let _ = stream_tasks.1.await;
+            let _ = tx.send(ExecutionEvent::Timeout).await;
+            return failed_stream_result(start.elapsed().as_millis() as u64);
+        }
+    };
+
+    let _ = stream_tasks.0.await;
+    let _ = stream_tasks.1.await;
+
+    let execution_time_ms = start.elapsed().as_millis() as u64;
+    let success = run_status.success();
+    let _ = tx
+        .send(ExecutionEvent::Done {
+            success,
+            execution_time_ms,
+        })
+        .await;
+
     ExecutionResult {
-        stdout: String::from_utf8_lossy(&run_output.stdout).to_string(),
-        stderr: String::from_utf8_lossy(&run_output.stderr).to_string(),
-        success: run_output.status.success(),
-        execution_time_ms: start.elapsed().as_millis() as u64,
+        stdout: String::new(),
+        stderr: String::new(),
+        success,
+        execution_time_ms,
         error: None,
+        diagnostics: Vec::new(),
+    }
+}
+
+/// Shared terminal `ExecutionResult` for the streaming path's failure
+/// branches, which already reported the failure over `tx`.
+fn failed_stream_result(execution_time_ms: u64) -> ExecutionResult {
+    ExecutionResult {
+        stdout: String::new(),
+        stderr: String::new(),
+        success: false,
+        execution_time_ms,
+        error: None,
+        diagnostics: Vec::new(),
+    }
+}
+
+/// Spawns one task per pipe that reads it line-by-line and forwards each
+/// line as an `ExecutionEvent::Output` tagged with `phase`.
+fn spawn_stream_tasks(
+    stdout: tokio::process::ChildStdout,
+    stderr: tokio::process::ChildStderr,
+    phase: ExecutionPhase,
+    tx: mpsc::Sender<ExecutionEvent>,
+) -> (tokio::task::JoinHandle<()>, tokio::task::JoinHandle<()>) {
+    let stdout_tx = tx.clone();
+    let out_task = tokio::spawn(async move {
+        let mut lines = BufReader::new(stdout).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if stdout_tx
+                .send(ExecutionEvent::Output {
+                    phase,
+                    stream: StreamKind::Stdout,
+                    data: line,
+                })
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+    });
+
+    let err_task = tokio::spawn(async move {
+        let mut lines = BufReader::new(stderr).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if tx
+                .send(ExecutionEvent::Output {
+                    phase,
+                    stream: StreamKind::Stderr,
+                    data: line,
+                })
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+    });
+
+    (out_task, err_task)
+}
+
+/// Raw shape of one line of `rustc --error-format=json` output. Artifact
+/// notifications (`"$message_type":"artifact"`) don't have a `message`
+/// field and simply fail to deserialize here, so callers should parse each
+/// line independently and skip the ones that don't match.
+#[derive(Deserialize)]
+struct RawDiagnostic {
+    message: String,
+    code: Option<RawErrorCode>,
+    level: String,
+    spans: Vec<RawSpan>,
+    rendered: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawErrorCode {
+    code: String,
+}
+
+#[derive(Deserialize)]
+struct RawSpan {
+    line_start: u32,
+    line_end: u32,
+    column_start: u32,
+    column_end: u32,
+    label: Option<String>,
+}
+
+/// Parses each line of `rustc --error-format=json` stderr into a
+/// `Diagnostic`, silently skipping lines that aren't diagnostic objects
+/// (e.g. artifact notifications) or that fail to parse.
+fn parse_rustc_json_diagnostics(stderr: &str) -> Vec<Diagnostic> {
+    stderr
+        .lines()
+        .filter_map(|line| serde_json::from_str::<RawDiagnostic>(line).ok())
+        .map(|raw| Diagnostic {
+            level: match raw.level.as_str() {
+                "error" | "error: internal compiler error" => DiagnosticLevel::Error,
+                "warning" => DiagnosticLevel::Warning,
+                "note" => DiagnosticLevel::Note,
+                "help" => DiagnosticLevel::Help,
+                _ => DiagnosticLevel::Other,
+            },
+            message: raw.message,
+            code: raw.code.map(|c| c.code),
+            spans: raw
+                .spans
+                .into_iter()
+                .map(|s| DiagnosticSpan {
+                    line_start: s.line_start,
+                    line_end: s.line_end,
+                    column_start: s.column_start,
+                    column_end: s.column_end,
+                    label: s.label,
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Builds the human-readable stderr text we still hand back alongside the
+/// structured `diagnostics`, preferring rustc's own pretty-printed
+/// "rendered" text and falling back to the raw JSON stream if nothing
+/// parsed (e.g. rustc itself failed to start).
+fn render_diagnostics_text(raw_stderr: &str, diagnostics: &[Diagnostic]) -> String {
+    if diagnostics.is_empty() {
+        return raw_stderr.to_string();
+    }
+
+    raw_stderr
+        .lines()
+        .filter_map(|line| serde_json::from_str::<RawDiagnostic>(line).ok())
+        .filter_map(|raw| raw.rendered)
+        .collect::<Vec<_>>()
+        .join("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_line(message: &str, code: Option<&str>, rendered: &str) -> String {
+        serde_json::json!({
+            "message": message,
+            "code": code.map(|c| serde_json::json!({"code": c})),
+            "level": "error",
+            "spans": [{
+                "line_start": 2,
+                "line_end": 2,
+                "column_start": 5,
+                "column_end": 9,
+                "label": "expected `;`",
+            }],
+            "rendered": rendered,
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn parses_level_message_code_and_spans() {
+        let stderr = error_line("mismatched types", Some("E0308"), "error[E0308]: ...\n");
+        let diagnostics = parse_rustc_json_diagnostics(&stderr);
+
+        assert_eq!(diagnostics.len(), 1);
+        let diag = &diagnostics[0];
+        assert_eq!(diag.level, DiagnosticLevel::Error);
+        assert_eq!(diag.message, "mismatched types");
+        assert_eq!(diag.code.as_deref(), Some("E0308"));
+        assert_eq!(diag.spans.len(), 1);
+        assert_eq!(diag.spans[0].line_start, 2);
+        assert_eq!(diag.spans[0].label.as_deref(), Some("expected `;`"));
+    }
+
+    #[test]
+    fn ignores_lines_that_are_not_json_diagnostics() {
+        // rustc's --error-format=json stream can be interleaved with plain
+        // text (e.g. a linker's own stderr) that isn't a diagnostic line.
+        let stderr = format!(
+            "note: some plain linker output\n{}\n",
+            error_line("unused variable", None, "warning: unused\n")
+        );
+        let diagnostics = parse_rustc_json_diagnostics(&stderr);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, None);
+    }
+
+    #[test]
+    fn render_diagnostics_text_prefers_rendered_field() {
+        let stderr = error_line("mismatched types", Some("E0308"), "error[E0308]: pretty\n");
+        let diagnostics = parse_rustc_json_diagnostics(&stderr);
+        let rendered = render_diagnostics_text(&stderr, &diagnostics);
+
+        assert_eq!(rendered, "error[E0308]: pretty\n");
+    }
+
+    #[test]
+    fn render_diagnostics_text_falls_back_to_raw_when_nothing_parsed() {
+        let stderr = "error: rustc itself failed to start\n";
+        let diagnostics = parse_rustc_json_diagnostics(stderr);
+
+        assert!(diagnostics.is_empty());
+        assert_eq!(render_diagnostics_text(stderr, &diagnostics), stderr);
+    }
+
+    #[test]
+    fn cargo_manifest_with_no_dependencies() {
+        let manifest = cargo_manifest(&[]);
+        assert_eq!(
+            manifest,
+            "[package]\nname = \"sandbox\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\n"
+        );
+    }
+
+    #[test]
+    fn cargo_manifest_pins_bare_crate_names_to_wildcard() {
+        let manifest = cargo_manifest(&["rand".to_string()]);
+        assert!(manifest.ends_with("rand = \"*\"\n"));
+    }
+
+    #[test]
+    fn cargo_manifest_passes_through_explicit_version_lines() {
+        let manifest = cargo_manifest(&["serde = { version = \"1\", features = [\"derive\"] }".to_string()]);
+        assert!(manifest.ends_with("serde = { version = \"1\", features = [\"derive\"] }\n"));
+    }
+
+    #[test]
+    fn cargo_manifest_renders_multiple_dependencies_in_order() {
+        let manifest = cargo_manifest(&["rand".to_string(), "serde = \"1\"".to_string()]);
+        let deps_section = manifest.split("[dependencies]\n").nth(1).unwrap();
+        let lines: Vec<&str> = deps_section.lines().collect();
+        assert_eq!(lines, vec!["rand = \"*\"", "serde = \"1\""]);
     }
 }